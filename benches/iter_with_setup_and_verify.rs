@@ -1,6 +1,6 @@
 extern crate criterion;
 
-use criterion::Criterion;
+use criterion::{BatchSize, Criterion};
 
 const SIZE: usize = 1024 * 1024;
 
@@ -19,3 +19,18 @@ fn dealloc() {
         b.iter_with_setup_and_verify(setup, iter_f, verify)
     });
 }
+
+// Same benchmark as `dealloc`, but using `iter_batched` so that the cost
+// of allocating `setup`'s `Vec` and dropping it afterwards isn't folded
+// into the measured sample.
+#[test]
+fn dealloc_batched() {
+    let setup = || (0..SIZE).collect::<Vec<_>>();
+    let routine = |mut v: Vec<_>| {
+        v[0] = 99;
+        v
+    };
+    Criterion::default().bench("dealloc_batched", |b| {
+        b.iter_batched(setup, routine, BatchSize::SmallInput)
+    });
+}
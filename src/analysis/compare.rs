@@ -0,0 +1,66 @@
+use stats::bivariate::Data;
+use stats::univariate::Sample;
+
+use estimate::{Estimate, Estimates, Statistic};
+use measurement::Measurement;
+use {fs, report};
+use {ConfidenceInterval, Criterion};
+
+// Scales `estimate`'s point, confidence interval, and standard error from
+// absolute measurements to the fractional change relative to `old_point`.
+fn relative(estimate: &Estimate, old_point: f64) -> Estimate {
+    Estimate {
+        confidence_interval: ConfidenceInterval {
+            confidence_level: estimate.confidence_interval.confidence_level,
+            lower_bound: (estimate.confidence_interval.lower_bound - old_point) / old_point,
+            upper_bound: (estimate.confidence_interval.upper_bound - old_point) / old_point,
+        },
+        point_estimate: (estimate.point_estimate - old_point) / old_point,
+        standard_error: estimate.standard_error / old_point,
+    }
+}
+
+// Compares `new_estimates` against the sample saved under the named
+// `baseline` directory, printing and returning the relative change (new
+// vs. baseline) for each statistic they have in common.
+pub fn common<M>(
+    id: &str,
+    baseline: &str,
+    _data: Data<f64, f64>,
+    _avg_times: &Sample<f64>,
+    new_estimates: &Estimates,
+    _criterion: &Criterion<M>,
+) -> Estimates where
+    M: Measurement,
+{
+    let path = format!(".criterion/{}/{}/sample.json", id, baseline);
+    let old: Option<(Vec<f64>, Vec<f64>)> = fs::load(&path).ok();
+    let (old_iters, old_times) = match old {
+        Some(sample) => sample,
+        None => return ::std::iter::empty().collect(),
+    };
+
+    let old_avg: Vec<f64> = old_iters.iter().zip(old_times.iter())
+        .map(|(&iters, &elapsed)| elapsed / iters)
+        .collect();
+    let old_avg_times = Sample::new(&old_avg);
+
+    let old_mean = old_avg_times.mean();
+    let old_median = old_avg_times.percentiles().median();
+    let old_points = [
+        (Statistic::Mean, old_mean),
+        (Statistic::Median, old_median),
+        (Statistic::MedianAbsDev, old_avg_times.median_abs_dev(Some(old_median))),
+        (Statistic::StdDev, old_avg_times.std_dev(Some(old_mean))),
+    ];
+
+    let rel_estimates: Estimates = old_points.iter()
+        .filter_map(|&(statistic, old_point)| {
+            new_estimates.get(&statistic).map(|estimate| (statistic, relative(estimate, old_point)))
+        })
+        .collect();
+
+    report::rel(&rel_estimates);
+
+    rel_estimates
+}
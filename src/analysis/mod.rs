@@ -10,11 +10,15 @@ use stats::univariate::{Sample,Percentiles};
 use stats::univariate::outliers::tukey::{LabeledSample, self};
 use time;
 
+use baseline::Baseline;
+use csv_report;
 use estimate::{Distributions, Estimates, Statistic};
+use measurement::Measurement;
+use message::OutgoingMessage;
 use program::Program;
 use routine::{Function, Routine};
-use {Bencher, ConfidenceInterval, Criterion, Estimate};
-use {format, fs, plot, report};
+use {Bencher, ConfidenceInterval, Criterion, Estimate, Throughput};
+use {format, fs, html, plot, report};
 use ::Fun;
 
 macro_rules! elapsed {
@@ -31,7 +35,17 @@ macro_rules! elapsed {
 
 mod compare;
 
-pub fn summarize(id: &str, criterion: &Criterion) {
+// Sends `message` down `criterion`'s `Connection`, if one is configured.
+// A no-op by default, so existing callers that never set up a connection
+// see no change in behavior.
+fn emit<M>(criterion: &Criterion<M>, message: OutgoingMessage) {
+    if let Some(ref connection) = criterion.connection {
+        let mut connection = connection.borrow_mut();
+        let _ = connection.send(criterion.message_format, &message);
+    }
+}
+
+pub fn summarize<M>(id: &str, criterion: &Criterion<M>) where M: Measurement {
     if criterion.plotting.is_enabled() {
         print!("Summarizing results of {}... ", id);
         plot::summarize(id);
@@ -39,18 +53,38 @@ pub fn summarize(id: &str, criterion: &Criterion) {
     } else {
         println!("Plotting disabled, skipping summarization");
     }
+
+    html::index().expect("failed to write the HTML report index");
 }
 
-pub fn function<F>(id: &str, f: F, criterion: &Criterion) where F: FnMut(&mut Bencher) {
-    common(id, &mut Function(f), criterion);
+pub fn function<M, F>(id: &str, f: F, criterion: &Criterion<M>) where
+    M: Measurement,
+    F: FnMut(&mut Bencher<M>),
+{
+    common(id, &mut Function(f), None, criterion);
 
     println!("");
 }
 
-pub fn functions<I>(id: &str,
-    funs: Vec<Fun<I>>,
+pub fn function_with_throughput<M, F>(
+    id: &str,
+    f: F,
+    throughput: Throughput,
+    criterion: &Criterion<M>,
+) where
+    M: Measurement,
+    F: FnMut(&mut Bencher<M>),
+{
+    common(id, &mut Function(f), Some(throughput), criterion);
+
+    println!("");
+}
+
+pub fn functions<M, I>(id: &str,
+    funs: Vec<Fun<M, I>>,
     input: &I,
-    criterion: &Criterion) -> Vec<(String, Percentiles<f64>)> where
+    criterion: &Criterion<M>) -> Vec<(String, Percentiles<f64>)> where
+    M: Measurement,
     I: fmt::Display
 {
     let mut percentiles = vec![];
@@ -58,7 +92,7 @@ pub fn functions<I>(id: &str,
         let id = format!("{}/{}", id, fun.n);
         let mut f = fun.f;
 
-        let this_percentiles = common(&id, &mut Function(|b| f(b, input)), criterion);
+        let this_percentiles = common(&id, &mut Function(|b| f(b, input)), None, criterion);
         percentiles.push((id, this_percentiles));
     }
 
@@ -66,37 +100,39 @@ pub fn functions<I>(id: &str,
     percentiles
 }
 
-pub fn function_with_inputs<I, F>(
+pub fn function_with_inputs<M, I, F>(
     id: &str,
     mut f: F,
     inputs: I,
-    criterion: &Criterion,
+    criterion: &Criterion<M>,
 ) where
-    F: FnMut(&mut Bencher, &I::Item),
+    M: Measurement,
+    F: FnMut(&mut Bencher<M>, &I::Item),
     I: IntoIterator,
     I::Item: fmt::Display,
 {
     for input in inputs {
         let id = format!("{}/{}", id, input);
 
-        common(&id, &mut Function(|b| f(b, &input)), criterion);
+        common(&id, &mut Function(|b| f(b, &input)), None, criterion);
     }
 
     summarize(id, criterion);
 }
 
-pub fn program(id: &str, prog: &mut Command, criterion: &Criterion) {
-    common(id, &mut Program::spawn(prog), criterion);
+pub fn program<M>(id: &str, prog: &mut Command, criterion: &Criterion<M>) where M: Measurement {
+    common(id, &mut Program::spawn(prog), None, criterion);
 
     println!("");
 }
 
-pub fn program_with_inputs<I, F>(
+pub fn program_with_inputs<M, I, F>(
     id: &str,
     mut prog: F,
     inputs: I,
-    criterion: &Criterion,
+    criterion: &Criterion<M>,
 ) where
+    M: Measurement,
     F: FnMut() -> Command,
     I: IntoIterator,
     I::Item: fmt::Display,
@@ -111,14 +147,28 @@ pub fn program_with_inputs<I, F>(
 }
 
 // Common analysis procedure
-fn common<R>(id: &str, routine: &mut R, criterion: &Criterion) -> Percentiles<f64> where
-    R: Routine,
+fn common<M, R>(
+    id: &str,
+    routine: &mut R,
+    throughput: Option<Throughput>,
+    criterion: &Criterion<M>,
+) -> Percentiles<f64> where
+    M: Measurement,
+    R: Routine<M>,
 {
     println!("Benchmarking {}", id);
+    emit(criterion, OutgoingMessage::BenchmarkStarted { id: id.to_owned() });
 
-    let (iters, times) = routine.sample(criterion);
+    let baseline = &criterion.baseline_directory;
+    if criterion.baseline == Baseline::CompareStrict && !base_dir_exists(id, baseline) {
+        panic!(
+            "Baseline '{}' for benchmark '{}' does not exist; cannot compare in strict mode",
+            baseline, id);
+    }
 
-    rename_new_dir_to_base(id);
+    let measurement = &criterion.measurement;
+    let (iters, values) = routine.sample(criterion);
+    let times = values.iter().map(|value| measurement.to_f64(value)).collect::<Vec<f64>>();
 
     let avg_times = iters.iter().zip(times.iter()).map(|(&iters, &elapsed)| {
         elapsed / iters
@@ -128,14 +178,15 @@ fn common<R>(id: &str, routine: &mut R, criterion: &Criterion) -> Percentiles<f6
     fs::mkdirp(&format!(".criterion/{}/new", id));
 
     let data = Data::new(&iters, &times);
-    let labeled_sample = outliers(id, avg_times);
+    let labeled_sample = outliers(id, avg_times, criterion);
+    let (los, lom, _, him, his) = labeled_sample.count();
     if criterion.plotting.is_enabled() {
         elapsed!(
             "Plotting the estimated sample PDF",
             plot::pdf(data, labeled_sample, id));
     }
-    let (distribution, slope) = regression(id, data, criterion);
-    let (mut distributions, mut estimates) = estimates(avg_times, criterion);
+    let (distribution, slope) = regression(id, data, throughput, criterion);
+    let (mut distributions, mut estimates) = estimates(avg_times, throughput, criterion);
 
     estimates.insert(Statistic::Slope, slope);
     distributions.insert(Statistic::Slope, distribution);
@@ -149,28 +200,101 @@ fn common<R>(id: &str, routine: &mut R, criterion: &Criterion) -> Percentiles<f6
                 id));
     }
 
-    fs::save(
-        &(data.x().as_slice(), data.y().as_slice()),
-        &format!(".criterion/{}/new/sample.json", id));
-    fs::save(&estimates, &format!(".criterion/{}/new/estimates.json", id));
+    // Written unconditionally: the statistics table and outlier summary
+    // don't depend on gnuplot, so `report.html` stays browsable even with
+    // plotting disabled. Only the `<img>` tags it embeds rely on the plots
+    // above having been generated.
+    let slope_ci = estimates.get(&Statistic::Slope).map(|estimate| estimate.confidence_interval);
+    let confidence_intervals = estimates.iter()
+        .filter(|&(&statistic, _)| statistic != Statistic::Slope)
+        .map(|(&statistic, estimate)| (statistic.to_string(), estimate.confidence_interval))
+        .collect();
+
+    let mut context = html::Context::new(id);
+    context.confidence_intervals = confidence_intervals;
+    context.slope = slope_ci;
+    context.outliers = (los, lom, him, his);
+
+    html::report(id, &context).expect("failed to write the HTML report");
+
+    if criterion.baseline != Baseline::Discard {
+        fs::save(
+            &(data.x().as_slice(), data.y().as_slice()),
+            &format!(".criterion/{}/new/sample.json", id));
+        fs::save(&estimates, &format!(".criterion/{}/new/estimates.json", id));
+        if let Some(throughput) = throughput {
+            fs::save(&throughput, &format!(".criterion/{}/new/throughput.json", id));
+        }
+    }
 
-    if base_dir_exists(id) {
-        compare::common(id, data, avg_times, &estimates, criterion);
+    let compare = match criterion.baseline {
+        Baseline::CompareLenient | Baseline::CompareStrict => true,
+        Baseline::Save | Baseline::Discard => false,
+    };
+    // Compare against the existing baseline, if any, *before* touching it,
+    // so a comparison mode never clobbers the reference measurement it's
+    // comparing against.
+    let rel_estimates = if compare && base_dir_exists(id, baseline) {
+        Some(compare::common(id, baseline, data, avg_times, &estimates, criterion))
+    } else {
+        None
+    };
+
+    // Only `Save` promotes this run's freshly-written `new/` into the
+    // named baseline directory; `Discard` never persists, and `Compare*`
+    // leaves the baseline it just compared against untouched.
+    if criterion.baseline == Baseline::Save {
+        rename_new_dir_to_base(id, baseline);
     }
 
+    if let Some(ref csv_path) = criterion.csv_path {
+        let unit = criterion.measurement.formatter().unit();
+        let (group_id, function_id) = split_id(id);
+        csv_report::save(
+            group_id,
+            function_id,
+            throughput.as_ref(),
+            unit,
+            &iters,
+            &estimates,
+            rel_estimates.as_ref(),
+            csv_path)
+            .expect("failed to write CSV report");
+    }
+
+    emit(criterion, OutgoingMessage::BenchmarkComplete {
+        id: id.to_owned(),
+        iters: iters.clone(),
+        times: times.clone(),
+        estimates: estimates.clone(),
+    });
+
     avg_times.percentiles()
 }
 
-fn base_dir_exists(id: &str) -> bool {
-    Path::new(&format!(".criterion/{}/base", id)).exists()
+fn base_dir_exists(id: &str, baseline: &str) -> bool {
+    Path::new(&format!(".criterion/{}/{}", id, baseline)).exists()
+}
+
+// Splits a benchmark id into its group and function parts for the CSV
+// report, matching how `functions()`/`function_with_inputs()` build nested
+// ids via `format!("{}/{}", id, ...)`. Ungrouped ids have no function part.
+fn split_id(id: &str) -> (&str, Option<&str>) {
+    match id.find('/') {
+        Some(i) => (&id[..i], Some(&id[i + 1..])),
+        None => (id, None),
+    }
 }
 
 // Performs a simple linear regression on the sample
-fn regression(
+fn regression<M>(
     id: &str,
     data: Data<f64, f64>,
-    criterion: &Criterion,
-) -> (Distribution<f64>, Estimate) {
+    throughput: Option<Throughput>,
+    criterion: &Criterion<M>,
+) -> (Distribution<f64>, Estimate) where
+    M: Measurement,
+{
     let cl = criterion.confidence_level;
 
     println!("> Performing linear regression");
@@ -185,7 +309,11 @@ fn regression(
 
     let (lb_, ub_) = (Slope(lb), Slope(ub));
 
-    report::regression(data, (lb_, ub_));
+    report::regression(criterion.measurement.formatter(), throughput.as_ref(), data, (lb_, ub_));
+    emit(criterion, OutgoingMessage::RegressionAnalyzed {
+        slope_point_estimate: point.0,
+        slope_confidence_interval: (lb, ub),
+    });
 
     if criterion.plotting.is_enabled() {
         elapsed!(
@@ -209,20 +337,37 @@ fn regression(
 }
 
 // Classifies the outliers in the sample
-fn outliers<'a>(id: &str, avg_times: &'a Sample<f64>) -> LabeledSample<'a, f64> {
+fn outliers<'a, M>(
+    id: &str,
+    avg_times: &'a Sample<f64>,
+    criterion: &Criterion<M>,
+) -> LabeledSample<'a, f64> {
     let sample = tukey::classify(avg_times);
 
     report::outliers(sample);
     fs::save(&sample.fences(), &format!(".criterion/{}/new/tukey.json", id));
 
+    let (lo_severe, lo_mild, hi_mild, hi_severe) = sample.fences();
+    let (los, lom, _, him, his) = sample.count();
+    emit(criterion, OutgoingMessage::OutliersFound {
+        fences: (lo_severe, lo_mild, hi_mild, hi_severe),
+        low_severe: los,
+        low_mild: lom,
+        high_mild: him,
+        high_severe: his,
+    });
+
     sample
 }
 
 // Estimates the statistics of the population from the sample
-fn estimates(
+fn estimates<M>(
     avg_times: &Sample<f64>,
-    criterion: &Criterion,
-) -> (Distributions, Estimates) {
+    throughput: Option<Throughput>,
+    criterion: &Criterion<M>,
+) -> (Distributions, Estimates) where
+    M: Measurement,
+{
     fn stats(sample: &Sample<f64>) -> (f64, f64, f64, f64) {
         let mean = sample.mean();
         let std_dev = sample.std_dev(Some(mean));
@@ -260,14 +405,17 @@ fn estimates(
     }).zip(distributions.into_iter()).collect();
     let estimates = Estimate::new(&distributions, &points, cl);
 
-    report::abs(&estimates);
+    report::abs(criterion.measurement.formatter(), throughput.as_ref(), &estimates);
+    emit(criterion, OutgoingMessage::EstimatesComplete {
+        estimates: estimates.clone(),
+    });
 
     (distributions, estimates)
 }
 
-fn rename_new_dir_to_base(id: &str) {
+fn rename_new_dir_to_base(id: &str, baseline: &str) {
     let root_dir = Path::new(".criterion").join(id);
-    let base_dir = root_dir.join("base");
+    let base_dir = root_dir.join(baseline);
     let new_dir = root_dir.join("new");
 
     if base_dir.exists() { fs::rmrf(&base_dir) }
@@ -0,0 +1,44 @@
+//! Controls how a benchmark run interacts with a saved baseline.
+
+/// How a benchmark's results should be saved, compared against, or
+/// discarded relative to a named baseline directory under `.criterion`.
+///
+/// The baseline directory used is `Criterion::baseline_directory`, which
+/// defaults to `"base"`; it can be overridden (e.g. via `--save-baseline
+/// main` / `--baseline main`) to keep several named baselines around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Baseline {
+    /// Write this run's results into the baseline directory, without
+    /// comparing against whatever is already there.
+    Save,
+
+    /// Measure the benchmark, but don't persist the results or compare
+    /// against a baseline.
+    Discard,
+
+    /// Compare against the baseline directory if it exists; if it
+    /// doesn't, just skip the comparison. This is the default.
+    CompareLenient,
+
+    /// Compare against the baseline directory, failing the benchmark if
+    /// it doesn't exist. Useful in CI, where a missing baseline usually
+    /// means the job is mis-configured rather than that there's nothing
+    /// to compare against.
+    CompareStrict,
+}
+
+impl Default for Baseline {
+    fn default() -> Baseline {
+        Baseline::CompareLenient
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_is_compare_lenient() {
+        assert_eq!(Baseline::CompareLenient, Baseline::default());
+    }
+}
@@ -0,0 +1,98 @@
+//! Controls how `Bencher::iter_batched` groups iterations into batches.
+
+use std::cmp;
+
+/// How many iterations belong in one batch of `Bencher::iter_batched`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchSize {
+    /// Split the iterations into a few (10) big batches, for routines whose
+    /// input and output are cheap to hold many of at once.
+    SmallInput,
+
+    /// Split the iterations into many (1000) small batches, so fewer
+    /// inputs and outputs are alive at once than with `SmallInput`, for
+    /// routines whose input or output is expensive to hold many of at once
+    /// (e.g. it owns a large heap allocation). Criterion.rs has no way to
+    /// know the actual size of an arbitrary `I`/`O`, so neither this nor
+    /// `SmallInput` bounds live memory directly.
+    LargeInput,
+
+    /// Use a batch size of one iteration. Use this when the input can't
+    /// be duplicated (it isn't `Clone`, or duplicating it would itself be
+    /// too expensive to amortize).
+    PerIteration,
+
+    /// Use at most `n` batches, regardless of how many iterations
+    /// Criterion.rs decides to run; each batch gets `iters / n` iterations
+    /// (rounded up), and the last batch gets whatever is left.
+    NumBatches(u64),
+
+    /// Use a fixed batch size of `n` iterations, regardless of how many
+    /// iterations Criterion.rs decides to run.
+    NumIterations(u64),
+}
+
+// `SmallInput`/`LargeInput` split `iters` into this many batches.
+const SMALL_INPUT_BATCHES: u64 = 10;
+const LARGE_INPUT_BATCHES: u64 = 1000;
+
+impl BatchSize {
+    // Given the number of iterations Criterion.rs wants to run, returns how
+    // many of those iterations belong in a single batch.
+    pub fn iters_per_batch(&self, iters: u64) -> u64 {
+        match *self {
+            BatchSize::SmallInput => divide_rounding_up(iters, SMALL_INPUT_BATCHES),
+            BatchSize::LargeInput => divide_rounding_up(iters, LARGE_INPUT_BATCHES),
+            BatchSize::PerIteration => 1,
+            BatchSize::NumBatches(n) => divide_rounding_up(iters, n),
+            BatchSize::NumIterations(n) => cmp::max(n, 1),
+        }
+    }
+}
+
+fn divide_rounding_up(iters: u64, batches: u64) -> u64 {
+    let batches = cmp::max(batches, 1);
+
+    cmp::max((iters + batches - 1) / batches, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_iteration_is_always_one() {
+        assert_eq!(1, BatchSize::PerIteration.iters_per_batch(1000));
+    }
+
+    #[test]
+    fn num_batches_rounds_up() {
+        assert_eq!(4, BatchSize::NumBatches(3).iters_per_batch(10));
+        assert_eq!(10, BatchSize::NumBatches(0).iters_per_batch(10));
+    }
+
+    #[test]
+    fn num_iterations_is_fixed() {
+        assert_eq!(7, BatchSize::NumIterations(7).iters_per_batch(1000));
+        assert_eq!(1, BatchSize::NumIterations(0).iters_per_batch(1000));
+    }
+
+    #[test]
+    fn small_input_splits_into_ten_batches() {
+        assert_eq!(100, BatchSize::SmallInput.iters_per_batch(1000));
+    }
+
+    #[test]
+    fn large_input_uses_smaller_batches_than_small_input() {
+        let small = BatchSize::SmallInput.iters_per_batch(1_000_000);
+        let large = BatchSize::LargeInput.iters_per_batch(1_000_000);
+
+        assert!(large <= small);
+    }
+
+    #[test]
+    fn batch_size_is_never_zero_even_for_zero_iterations() {
+        assert_eq!(1, BatchSize::SmallInput.iters_per_batch(0));
+        assert_eq!(1, BatchSize::NumBatches(5).iters_per_batch(0));
+    }
+}
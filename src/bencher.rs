@@ -0,0 +1,90 @@
+use std::cmp;
+
+use measurement::Measurement;
+use BatchSize;
+
+/// The timing harness handed to the closure passed to `Criterion::bench`.
+///
+/// `Bencher` is the lowest-level piece that actually calls
+/// `Measurement::start`/`Measurement::end` around the routine being timed;
+/// everything above it (`Routine`, `common()`) just decides how many
+/// iterations to ask for.
+pub struct Bencher<'a, M: Measurement + 'a> {
+    pub(crate) iters: u64,
+    pub(crate) value: M::Value,
+    pub(crate) measurement: &'a M,
+}
+
+impl<'a, M: Measurement> Bencher<'a, M> {
+    /// Times `routine` for `self.iters` iterations.
+    pub fn iter<O, R>(&mut self, mut routine: R) where R: FnMut() -> O {
+        let start = self.measurement.start();
+        for _ in 0..self.iters {
+            routine();
+        }
+        self.value = self.measurement.end(start);
+    }
+
+    /// Times `routine`, running `setup` before and `verify` after each
+    /// iteration, both outside the timed region.
+    pub fn iter_with_setup_and_verify<I, O, S, R, V>(
+        &mut self,
+        mut setup: S,
+        mut routine: R,
+        mut verify: V,
+    ) where
+        S: FnMut() -> I,
+        R: FnMut(I) -> O,
+        V: FnMut(O),
+    {
+        self.value = self.measurement.zero();
+
+        for _ in 0..self.iters {
+            let input = setup();
+
+            let start = self.measurement.start();
+            let output = routine(input);
+            let elapsed = self.measurement.end(start);
+
+            verify(output);
+
+            self.value = self.measurement.add(&self.value, &elapsed);
+        }
+    }
+
+    /// Times `routine` over batches of iterations. `setup` is run for a
+    /// whole batch *before* the timer starts; the batch's outputs are
+    /// collected and only dropped *after* the timer stops, so that
+    /// per-iteration setup cost and large `Drop`s don't leak into the
+    /// measured sample. `size` controls how many iterations go into each
+    /// batch; see `BatchSize`.
+    pub fn iter_batched<I, O, S, R>(
+        &mut self,
+        mut setup: S,
+        mut routine: R,
+        size: BatchSize,
+    ) where
+        S: FnMut() -> I,
+        R: FnMut(I) -> O,
+    {
+        self.value = self.measurement.zero();
+
+        let iters_per_batch = size.iters_per_batch(self.iters);
+        let mut iters_remaining = self.iters;
+        while iters_remaining > 0 {
+            let batch_size = cmp::min(iters_per_batch, iters_remaining);
+            let batch_size = cmp::max(batch_size, 1);
+
+            let inputs: Vec<I> = (0..batch_size).map(|_| setup()).collect();
+
+            let start = self.measurement.start();
+            let outputs: Vec<O> = inputs.into_iter().map(&mut routine).collect();
+            let elapsed = self.measurement.end(start);
+
+            drop(outputs);
+
+            self.value = self.measurement.add(&self.value, &elapsed);
+            iters_remaining -= batch_size;
+        }
+    }
+}
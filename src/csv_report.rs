@@ -0,0 +1,108 @@
+//! Export estimates (and, when available, relative-change data) to a
+//! user-specified CSV file.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+use estimate::Estimates;
+use Throughput;
+
+const HEADER: &'static str = "group,function,value,throughput_num,throughput_type,\
+sample_measured_value,unit,iteration_count";
+
+// Quotes `field` per RFC 4180 if it contains a character (`,`, `"`, or a
+// newline) that would otherwise corrupt the CSV's column alignment.
+fn quote(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+// Appends one row per statistic in `estimates` (the absolute measurement,
+// in `unit`) and, when `rel_estimates` is given, one more row per
+// statistic (the relative change versus the baseline, in "%").
+pub fn save(
+    group_id: &str,
+    function_id: Option<&str>,
+    throughput: Option<&Throughput>,
+    unit: &str,
+    iters: &[f64],
+    estimates: &Estimates,
+    rel_estimates: Option<&Estimates>,
+    path: &Path,
+) -> io::Result<()> {
+    let write_header = !path.exists();
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+    if write_header {
+        writeln!(file, "{}", HEADER)?;
+    }
+
+    let (throughput_num, throughput_type) = match throughput {
+        Some(&Throughput::Bytes(n)) => (n.to_string(), "bytes"),
+        Some(&Throughput::Elements(n)) => (n.to_string(), "elements"),
+        None => (String::new(), ""),
+    };
+
+    let iteration_count = iters.iter().sum::<f64>();
+    let group_id = quote(group_id);
+    let function_id = quote(function_id.unwrap_or(""));
+
+    for (&statistic, estimate) in estimates.iter() {
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{},{}",
+            group_id,
+            function_id,
+            quote(&statistic.to_string()),
+            throughput_num,
+            throughput_type,
+            estimate.point_estimate,
+            unit,
+            iteration_count,
+            )?;
+    }
+
+    if let Some(rel_estimates) = rel_estimates {
+        for (&statistic, estimate) in rel_estimates.iter() {
+            writeln!(
+                file,
+                "{},{},{},{},{},{},{},{}",
+                group_id,
+                function_id,
+                quote(&statistic.to_string()),
+                throughput_num,
+                throughput_type,
+                estimate.point_estimate * 100.0,
+                "%",
+                iteration_count,
+                )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_leaves_plain_fields_untouched() {
+        assert_eq!("my_benchmark", quote("my_benchmark"));
+    }
+
+    #[test]
+    fn quote_wraps_fields_containing_a_comma() {
+        assert_eq!("\"a,b\"", quote("a,b"));
+    }
+
+    #[test]
+    fn quote_escapes_embedded_quotes() {
+        assert_eq!("\"say \"\"hi\"\"\"", quote("say \"hi\""));
+    }
+}
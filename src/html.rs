@@ -0,0 +1,136 @@
+//! A standalone HTML report, rendered alongside the gnuplot PNGs that
+//! `plot::summarize` produces.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+use tera::{Context as TeraContext, Tera};
+
+use ConfidenceInterval;
+
+const REPORT_TEMPLATE: &'static str = include_str!("html/report.html.tera");
+const INDEX_TEMPLATE: &'static str = include_str!("html/index.html.tera");
+
+const DEFAULT_THUMBNAIL_WIDTH: u32 = 1280;
+const DEFAULT_THUMBNAIL_HEIGHT: u32 = 720;
+
+/// Everything the `report.html` template needs to render one benchmark's
+/// page.
+#[derive(Serialize)]
+pub struct Context {
+    pub id: String,
+    pub thumbnail_width: u32,
+    pub thumbnail_height: u32,
+    /// `(statistic name, confidence interval)` for mean/median/MAD/std-dev.
+    pub confidence_intervals: Vec<(String, ConfidenceInterval)>,
+    pub slope: Option<ConfidenceInterval>,
+    /// `(low severe, low mild, high mild, high severe)` outlier counts.
+    pub outliers: (usize, usize, usize, usize),
+}
+
+impl Context {
+    pub fn new(id: &str) -> Context {
+        Context {
+            id: id.to_owned(),
+            thumbnail_width: DEFAULT_THUMBNAIL_WIDTH,
+            thumbnail_height: DEFAULT_THUMBNAIL_HEIGHT,
+            confidence_intervals: vec![],
+            slope: None,
+            outliers: (0, 0, 0, 0),
+        }
+    }
+}
+
+// Renders and writes `.criterion/{id}/report.html`.
+pub fn report(id: &str, context: &Context) -> io::Result<()> {
+    let mut tera = Tera::default();
+    tera.add_raw_template("report.html", REPORT_TEMPLATE)
+        .expect("invalid report.html template");
+
+    let mut tera_context = TeraContext::new();
+    tera_context.insert("context", context);
+    let rendered = tera.render("report.html", &tera_context)
+        .expect("failed to render report.html");
+
+    let mut file = File::create(Path::new(".criterion").join(id).join("report.html"))?;
+    file.write_all(rendered.as_bytes())
+}
+
+// Recursively walks `dir` looking for benchmark directories that contain a
+// `report.html`, pushing their id (the path below `.criterion`, joined with
+// `/`) onto `ids`. Groups and multi-input benchmarks nest their ids (e.g.
+// `group/function`), so a single-level `read_dir` would miss them.
+fn collect_ids(dir: &Path, prefix: &str, ids: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name().into_string().unwrap_or_default();
+        let id = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+
+        if path.join("report.html").exists() {
+            ids.push(id.clone());
+        }
+
+        collect_ids(&path, &id, ids)?;
+    }
+    Ok(())
+}
+
+// Scans `.criterion` for benchmarks that already have a `report.html` and
+// (re)writes the top-level index linking all of them.
+pub fn index() -> io::Result<()> {
+    let mut ids = vec![];
+    let _ = collect_ids(Path::new(".criterion"), "", &mut ids);
+    ids.sort();
+
+    let mut tera = Tera::default();
+    tera.add_raw_template("index.html", INDEX_TEMPLATE)
+        .expect("invalid index.html template");
+
+    let mut tera_context = TeraContext::new();
+    tera_context.insert("ids", &ids);
+    let rendered = tera.render("index.html", &tera_context)
+        .expect("failed to render index.html");
+
+    let mut file = File::create(Path::new(".criterion").join("report.html"))?;
+    file.write_all(rendered.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn touch_report_html(dir: &Path) {
+        fs::create_dir_all(dir).unwrap();
+        File::create(dir.join("report.html")).unwrap();
+    }
+
+    #[test]
+    fn collect_ids_finds_nested_group_benchmarks() {
+        let root = env::temp_dir().join(format!("criterion-html-test-{}", ::std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        touch_report_html(&root.join("solo"));
+        touch_report_html(&root.join("group").join("a"));
+        touch_report_html(&root.join("group").join("b"));
+        fs::create_dir_all(root.join("group").join("new")).unwrap();
+
+        let mut ids = vec![];
+        collect_ids(&root, "", &mut ids).unwrap();
+        ids.sort();
+
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(vec!["group/a", "group/b", "solo"], ids);
+    }
+}
@@ -0,0 +1,200 @@
+use time;
+
+use format;
+use Throughput;
+
+/// A trait that abstracts over the "thing" a benchmark measures.
+///
+/// `Intermediate` is whatever `start` hands back to be paired up with the
+/// matching `end` call (e.g. a cycle-counter snapshot), while `Value` is
+/// the completed measurement for a single sample (e.g. the number of
+/// cycles elapsed). `Value`s for the individual iterations of a sample are
+/// combined with `add`, starting from `zero`.
+pub trait Measurement {
+    /// The in-progress measurement captured by `start`.
+    type Intermediate;
+    /// A completed measurement, produced by `end`.
+    type Value;
+
+    /// Start a new measurement.
+    fn start(&self) -> Self::Intermediate;
+
+    /// Complete a measurement previously started with `start`.
+    fn end(&self, i: Self::Intermediate) -> Self::Value;
+
+    /// Combine two values, e.g. to accumulate the iterations of a batch.
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value;
+
+    /// The identity element for `add`.
+    fn zero(&self) -> Self::Value;
+
+    /// Convert a `Value` to an `f64`, so it can be fed into the
+    /// (measurement-agnostic) statistics code.
+    fn to_f64(&self, value: &Self::Value) -> f64;
+
+    /// The `ValueFormatter` used to render this measurement's values for
+    /// humans.
+    fn formatter(&self) -> &ValueFormatter;
+}
+
+/// Formats the `f64` values produced by a `Measurement` for human-readable
+/// output, picking a unit so that the number has only a few significant
+/// digits (e.g. wall-clock nanoseconds are scaled to ns/us/ms/s).
+pub trait ValueFormatter {
+    /// Format a single measured value.
+    fn format_value(&self, value: f64) -> String;
+
+    /// Format a throughput figure derived from a measured value (e.g.
+    /// bytes/sec or elements/sec).
+    fn format_throughput(&self, throughput: &Throughput, value: f64) -> String;
+
+    /// The base unit values are expressed in before scaling, e.g. `"ns"`
+    /// for `WallTime`. Used by artifacts (like the CSV report) that record
+    /// a single raw number rather than a human-scaled string.
+    fn unit(&self) -> &'static str;
+}
+
+/// The default `Measurement`: wall-clock time, in nanoseconds, via
+/// `time::precise_time_ns`. Every `Criterion` uses this unless a different
+/// `Measurement` is configured.
+pub struct WallTime;
+
+impl Measurement for WallTime {
+    type Intermediate = u64;
+    type Value = f64;
+
+    fn start(&self) -> u64 {
+        time::precise_time_ns()
+    }
+
+    fn end(&self, i: u64) -> f64 {
+        (time::precise_time_ns() - i) as f64
+    }
+
+    fn add(&self, v1: &f64, v2: &f64) -> f64 {
+        v1 + v2
+    }
+
+    fn zero(&self) -> f64 {
+        0.0
+    }
+
+    fn to_f64(&self, value: &f64) -> f64 {
+        *value
+    }
+
+    fn formatter(&self) -> &ValueFormatter {
+        &WALL_TIME_FORMATTER
+    }
+}
+
+struct WallTimeFormatter;
+
+static WALL_TIME_FORMATTER: WallTimeFormatter = WallTimeFormatter;
+
+impl ValueFormatter for WallTimeFormatter {
+    fn format_value(&self, value: f64) -> String {
+        format::time(value)
+    }
+
+    fn format_throughput(&self, throughput: &Throughput, value: f64) -> String {
+        match *throughput {
+            Throughput::Bytes(bytes) => format_bytes_per_second(bytes, value),
+            Throughput::Elements(elems) => format_elements_per_second(elems, value),
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        "ns"
+    }
+}
+
+// `value` is nanoseconds per iteration, as produced by `WallTime::to_f64`.
+fn format_bytes_per_second(bytes: u64, value: f64) -> String {
+    let bytes_per_second = bytes as f64 * 1e9 / value;
+    let (scaled, unit) = scale_bytes_per_second(bytes_per_second);
+
+    format!("{:>6.4} {}", scaled, unit)
+}
+
+fn scale_bytes_per_second(bytes_per_second: f64) -> (f64, &'static str) {
+    const KIB: f64 = 1024.0;
+    const MIB: f64 = KIB * 1024.0;
+    const GIB: f64 = MIB * 1024.0;
+
+    if bytes_per_second < KIB {
+        (bytes_per_second, "  B/s")
+    } else if bytes_per_second < MIB {
+        (bytes_per_second / KIB, "KiB/s")
+    } else if bytes_per_second < GIB {
+        (bytes_per_second / MIB, "MiB/s")
+    } else {
+        (bytes_per_second / GIB, "GiB/s")
+    }
+}
+
+fn format_elements_per_second(elems: u64, value: f64) -> String {
+    let elements_per_second = elems as f64 * 1e9 / value;
+    let (scaled, unit) = scale_elements_per_second(elements_per_second);
+
+    format!("{:>6.4} {}elem/s", scaled, unit)
+}
+
+fn scale_elements_per_second(elements_per_second: f64) -> (f64, &'static str) {
+    const K: f64 = 1e3;
+    const M: f64 = 1e6;
+    const G: f64 = 1e9;
+
+    if elements_per_second < K {
+        (elements_per_second, "")
+    } else if elements_per_second < M {
+        (elements_per_second / K, "K")
+    } else if elements_per_second < G {
+        (elements_per_second / M, "M")
+    } else {
+        (elements_per_second / G, "G")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wall_time_add_and_zero() {
+        let m = WallTime;
+
+        assert_eq!(0.0, m.zero());
+        assert_eq!(3.0, m.add(&1.0, &2.0));
+        assert_eq!(2.5, m.to_f64(&2.5));
+    }
+
+    #[test]
+    fn wall_time_formatter_unit_is_nanoseconds() {
+        let m = WallTime;
+
+        assert_eq!("ns", m.formatter().unit());
+    }
+
+    #[test]
+    fn bytes_per_second_scales_to_mebibytes() {
+        // 1 MiB/iteration at 1ms/iteration -> 1 MiB * 1000/s = 1000 MiB/s
+        let formatted = WALL_TIME_FORMATTER.format_throughput(&Throughput::Bytes(1024 * 1024), 1e6);
+
+        assert!(formatted.ends_with("MiB/s"));
+    }
+
+    #[test]
+    fn bytes_per_second_stays_in_bytes_for_small_throughput() {
+        let formatted = WALL_TIME_FORMATTER.format_throughput(&Throughput::Bytes(1), 1e9);
+
+        assert!(formatted.ends_with("B/s"));
+    }
+
+    #[test]
+    fn elements_per_second_scales_to_kilo() {
+        let formatted = WALL_TIME_FORMATTER.format_throughput(&Throughput::Elements(10_000), 1e9);
+
+        assert!(formatted.ends_with("Kelem/s"));
+    }
+}
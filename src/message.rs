@@ -0,0 +1,123 @@
+//! A structured, machine-readable event stream for external tooling.
+
+use std::io::{self, Write};
+use std::net::TcpStream;
+
+use estimate::Estimates;
+
+/// How `OutgoingMessage`s are encoded on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// One JSON object per line.
+    Json,
+
+    /// `bincode`, with each message prefixed by its encoded length as a
+    /// little-endian `u32`.
+    Bincode,
+}
+
+/// A single structured event describing the progress of a benchmark.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OutgoingMessage {
+    /// A benchmark has started running.
+    BenchmarkStarted {
+        id: String,
+    },
+
+    /// The Tukey outlier classification of the benchmark's sample.
+    OutliersFound {
+        fences: (f64, f64, f64, f64),
+        low_severe: usize,
+        low_mild: usize,
+        high_mild: usize,
+        high_severe: usize,
+    },
+
+    /// The linear regression of `(iters, times)` has been computed.
+    RegressionAnalyzed {
+        slope_point_estimate: f64,
+        slope_confidence_interval: (f64, f64),
+    },
+
+    /// The absolute statistics (mean, median, etc.) of the sample have
+    /// been estimated.
+    EstimatesComplete {
+        estimates: Estimates,
+    },
+
+    /// A benchmark has finished; carries its raw `(iters, times)` sample
+    /// and its final absolute estimates (including the regression slope).
+    /// The relative change against a baseline is not included.
+    BenchmarkComplete {
+        id: String,
+        iters: Vec<f64>,
+        times: Vec<f64>,
+        estimates: Estimates,
+    },
+}
+
+/// Where the `OutgoingMessage` stream is sent.
+pub enum Connection {
+    /// Write messages to stdout.
+    Stdout(io::Stdout),
+
+    /// Write messages to a socket a supervising process is listening on.
+    Socket(TcpStream),
+}
+
+impl Connection {
+    /// Stream messages to a socket a supervising process is listening on.
+    pub fn tcp(stream: TcpStream) -> Connection {
+        Connection::Socket(stream)
+    }
+
+    fn writer(&mut self) -> &mut Write {
+        match *self {
+            Connection::Stdout(ref mut stdout) => stdout,
+            Connection::Socket(ref mut socket) => socket,
+        }
+    }
+
+    /// Serialize `message` in the given `format` and write it to this
+    /// connection.
+    pub fn send(&mut self, format: MessageFormat, message: &OutgoingMessage) -> io::Result<()> {
+        match format {
+            MessageFormat::Json => {
+                let line = ::serde_json::to_string(message)
+                    .expect("failed to serialize OutgoingMessage as JSON");
+                let writer = self.writer();
+                writer.write_all(line.as_bytes())?;
+                writer.write_all(b"\n")
+            }
+            MessageFormat::Bincode => {
+                let bytes = ::bincode::serialize(message, ::bincode::Infinite)
+                    .expect("failed to serialize OutgoingMessage as bincode");
+                let len = bytes.len() as u32;
+                let len_bytes = [len as u8, (len >> 8) as u8, (len >> 16) as u8, (len >> 24) as u8];
+
+                let writer = self.writer();
+                writer.write_all(&len_bytes)?;
+                writer.write_all(&bytes)
+            }
+        }
+    }
+}
+
+impl Default for Connection {
+    fn default() -> Connection {
+        Connection::Stdout(io::stdout())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_default_is_stdout() {
+        match Connection::default() {
+            Connection::Stdout(_) => {}
+            Connection::Socket(_) => panic!("expected Connection::default() to be Stdout"),
+        }
+    }
+}
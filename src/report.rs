@@ -4,14 +4,23 @@ use stats::univariate::outliers::tukey::LabeledSample;
 
 use format;
 use estimate::Estimates;
+use measurement::ValueFormatter;
+use Throughput;
 
-pub fn abs(estimates: &Estimates) {
+pub fn abs(formatter: &ValueFormatter, throughput: Option<&Throughput>, estimates: &Estimates) {
     for (&statistic, estimate) in estimates.iter() {
         let ci = estimate.confidence_interval;
-        let lb = format::time(ci.lower_bound);
-        let ub = format::time(ci.upper_bound);
+        let lb = formatter.format_value(ci.lower_bound);
+        let ub = formatter.format_value(ci.upper_bound);
 
         println!("  > {:>6} [{} {}]", statistic, lb, ub);
+
+        if let Some(throughput) = throughput {
+            let lb_thrpt = formatter.format_throughput(throughput, ci.lower_bound);
+            let ub_thrpt = formatter.format_throughput(throughput, ci.upper_bound);
+
+            println!("  > {:>6} [{} {}]", "thrpt", ub_thrpt, lb_thrpt);
+        }
     }
 }
 
@@ -53,14 +62,28 @@ pub fn outliers(sample: LabeledSample<f64>) {
     print(his, "high severe");
 }
 
-pub fn regression(data: Data<f64, f64>, (lb, ub): (Slope<f64>, Slope<f64>)) {
+pub fn regression(
+    formatter: &ValueFormatter,
+    throughput: Option<&Throughput>,
+    data: Data<f64, f64>,
+    (lb, ub): (Slope<f64>, Slope<f64>),
+) {
     println!(
         "  > {:>6} [{} {}]",
         "slope",
-        format::time(lb.0),
-        format::time(ub.0),
+        formatter.format_value(lb.0),
+        formatter.format_value(ub.0),
         );
 
+    if let Some(throughput) = throughput {
+        println!(
+            "  > {:>6} [{} {}]",
+            "thrpt",
+            formatter.format_throughput(throughput, ub.0),
+            formatter.format_throughput(throughput, lb.0),
+            );
+    }
+
     println!("  > {:>6}  {:0.7} {:0.7}",
              "R^2",
              lb.r_squared(data),
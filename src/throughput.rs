@@ -0,0 +1,32 @@
+//! Support for reporting a benchmark's throughput in addition to its
+//! per-iteration timing.
+
+/// The throughput processed by one iteration of a benchmark, used to
+/// derive a bytes/sec or elements/sec estimate alongside the usual
+/// per-iteration timing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Throughput {
+    /// Measure throughput in terms of bytes/sec, for benchmarks that
+    /// processes the given number of bytes per iteration.
+    Bytes(u64),
+
+    /// Measure throughput in terms of elements/sec, for benchmarks that
+    /// processes the given number of elements (items, records, ...) per
+    /// iteration.
+    Elements(u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_and_elements_are_distinct_even_with_the_same_count() {
+        assert_ne!(Throughput::Bytes(1), Throughput::Elements(1));
+    }
+
+    #[test]
+    fn equal_variant_and_count_compare_equal() {
+        assert_eq!(Throughput::Bytes(512), Throughput::Bytes(512));
+    }
+}